@@ -0,0 +1,122 @@
+//! Shared rendering of parser errors into line/column-aware diagnostics, used by
+//! both the CLI (fancy caret-underlined snippets) and the Python binding (structured
+//! `.errors` dicts) so the two entry points stay in sync.
+
+use partiql_parser::lexer::LexError;
+use partiql_parser::{LineOffsetTracker, ParserError};
+
+/// One parser error translated from a raw byte span into a line/column location,
+/// ready to render as a snippet or hand back to Python as a dict.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Translate every error carried by `error` into a `Diagnostic`, resolving byte
+/// offsets against `query` with a `LineOffsetTracker`.
+pub fn diagnostics_for(query: &str, error: &ParserError) -> Vec<Diagnostic> {
+    let mut tracker = LineOffsetTracker::default();
+    error
+        .errors
+        .iter()
+        .map(|e| {
+            let start = e.location.start.0 as usize;
+            let end = e.location.end.0 as usize;
+            let (line, column) = tracker.at(query, e.location.start);
+            Diagnostic {
+                message: e.to_string(),
+                start_byte: start,
+                end_byte: end,
+                line,
+                column,
+            }
+        })
+        .collect()
+}
+
+/// Translate a single lexer error into a `Diagnostic`, the token-scanning analogue of
+/// `diagnostics_for` for parser errors.
+pub fn diagnostic_for_lex_error(query: &str, error: &LexError) -> Diagnostic {
+    let mut tracker = LineOffsetTracker::default();
+    let start = error.location.start.0 as usize;
+    let end = error.location.end.0 as usize;
+    let (line, column) = tracker.at(query, error.location.start);
+    Diagnostic {
+        message: error.to_string(),
+        start_byte: start,
+        end_byte: end,
+        line,
+        column,
+    }
+}
+
+/// Render a diagnostic as a caret-underlined snippet of the offending source line,
+/// in the style of `miette`'s fancy single-span reports.
+pub fn render_snippet(query: &str, diagnostic: &Diagnostic) -> String {
+    let line_text = query.lines().nth(diagnostic.line.saturating_sub(1)).unwrap_or("");
+    let caret_start = diagnostic.column.saturating_sub(1);
+    let caret_width = (diagnostic.end_byte - diagnostic.start_byte).max(1);
+
+    format!(
+        "error: {message}\n  --> line {line}:{column}\n   |\n   | {line_text}\n   | {pad}{carets}",
+        message = diagnostic.message,
+        line = diagnostic.line,
+        column = diagnostic.column,
+        line_text = line_text,
+        pad = " ".repeat(caret_start),
+        carets = "^".repeat(caret_width),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use partiql_parser::Parser;
+
+    #[test]
+    fn diagnostics_for_locates_the_offending_token_by_line_and_column() {
+        let query = "SELECT a\nFROM";
+        let error = Parser::default().parse(query).unwrap_err();
+        let diagnostics = diagnostics_for(query, &error);
+        assert!(!diagnostics.is_empty());
+        let diagnostic = &diagnostics[0];
+        assert!(diagnostic.line >= 1);
+        assert!(diagnostic.column >= 1);
+        assert!(diagnostic.start_byte <= diagnostic.end_byte);
+    }
+
+    #[test]
+    fn render_snippet_underlines_the_exact_span_on_the_right_line() {
+        let diagnostic = Diagnostic {
+            message: "unexpected token".to_string(),
+            start_byte: 7,
+            end_byte: 11,
+            line: 1,
+            column: 8,
+        };
+        let rendered = render_snippet("SELECT FROM t", &diagnostic);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[3], "   | SELECT FROM t");
+        assert_eq!(lines[4], "   |        ^^^^");
+    }
+
+    #[test]
+    fn render_snippet_handles_multiline_queries() {
+        let diagnostic = Diagnostic {
+            message: "unexpected end of input".to_string(),
+            start_byte: 13,
+            end_byte: 13,
+            line: 2,
+            column: 5,
+        };
+        let rendered = render_snippet("SELECT a\nFROM", &diagnostic);
+        assert!(rendered.contains("line 2:5"));
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[3], "   | FROM");
+        assert_eq!(lines[4], "   |     ^");
+    }
+}
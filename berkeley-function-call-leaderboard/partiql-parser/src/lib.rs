@@ -1,18 +1,348 @@
+pub mod diagnostics;
+pub mod scanner;
+
 use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
 use partiql_parser::Parser;
+use partiql_logical_planner::lower;
+use partiql_eval::env::basic::MapBindings;
+use partiql_eval::plan::EvaluatorPlanner;
+use partiql_value::{List as ValueList, Tuple, Value};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+
+pyo3::create_exception!(partiql_parser, PartiQLSyntaxError, pyo3::exceptions::PyException);
+
+/// Build a `PartiQLSyntaxError` whose `.errors` attribute is a list of
+/// `{message, start_byte, end_byte, line, column}` dicts, one per diagnostic.
+fn syntax_error(py: Python, diagnostics: Vec<diagnostics::Diagnostic>) -> PyErr {
+    let py_errors = PyList::empty(py);
+    for diagnostic in &diagnostics {
+        let dict = PyDict::new(py);
+        let _ = dict.set_item("message", &diagnostic.message);
+        let _ = dict.set_item("start_byte", diagnostic.start_byte);
+        let _ = dict.set_item("end_byte", diagnostic.end_byte);
+        let _ = dict.set_item("line", diagnostic.line);
+        let _ = dict.set_item("column", diagnostic.column);
+        let _ = py_errors.append(dict);
+    }
+
+    let err = PartiQLSyntaxError::new_err("Failed to parse query");
+    if let Ok(value) = err.value(py).downcast::<pyo3::types::PyAny>() {
+        let _ = value.setattr("errors", py_errors);
+    }
+    err
+}
 
 #[pyfunction]
-fn parse_partiql(query: &str) -> PyResult<String> {
+fn parse_partiql(py: Python, query: &str) -> PyResult<String> {
     let parser = Parser::default();
     match parser.parse(query) {
         Ok(parsed) => Ok(format!("{:?}", parsed)),
-        Err(e) => Err(pyo3::exceptions::PyValueError::new_err(format!("Failed to parse query: {:?}", e))),
+        Err(e) => Err(syntax_error(py, diagnostics::diagnostics_for(query, &e))),
     }
 }
 
+/// Parse `query` and serialize the resulting AST, including each node's byte-span
+/// metadata from the parser's `LocationMap`, to a JSON string. Requires the `serde`
+/// feature of `partiql-parser`/`partiql-ast` so that `AstNode`/`Query` derive
+/// `Serialize`.
+#[pyfunction]
+fn parse_partiql_json(py: Python, query: &str) -> PyResult<String> {
+    let parser = Parser::default();
+    let parsed = parser
+        .parse(query)
+        .map_err(|e| syntax_error(py, diagnostics::diagnostics_for(query, &e)))?;
+
+    let spans: Vec<_> = parsed
+        .locations
+        .iter()
+        .map(|(id, location)| {
+            serde_json::json!({
+                "node_id": id,
+                "start_byte": location.start.0,
+                "end_byte": location.end.0,
+            })
+        })
+        .collect();
+
+    let document = serde_json::json!({
+        "ast": &parsed.ast,
+        "locations": spans,
+    });
+
+    serde_json::to_string(&document)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to serialize AST: {}", e)))
+}
+
+/// Same as `parse_partiql_json`, but walks the serialized document into a nested
+/// Python dict/list structure instead of returning a JSON string.
+#[pyfunction]
+fn parse_partiql_ast(py: Python, query: &str) -> PyResult<PyObject> {
+    let json = parse_partiql_json(py, query)?;
+    let document: serde_json::Value = serde_json::from_str(&json)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to read back AST: {}", e)))?;
+    json_to_py(py, &document)
+}
+
+/// Convert a `serde_json::Value` into the native Python object it represents.
+fn json_to_py(py: Python, value: &serde_json::Value) -> PyResult<PyObject> {
+    match value {
+        serde_json::Value::Null => Ok(py.None()),
+        serde_json::Value::Bool(b) => Ok(b.into_py(py)),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Ok(i.into_py(py))
+            } else {
+                Ok(n.as_f64().unwrap_or(0.0).into_py(py))
+            }
+        }
+        serde_json::Value::String(s) => Ok(s.into_py(py)),
+        serde_json::Value::Array(items) => {
+            let converted: PyResult<Vec<PyObject>> = items.iter().map(|v| json_to_py(py, v)).collect();
+            Ok(PyList::new(py, converted?).into_py(py))
+        }
+        serde_json::Value::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (key, v) in fields {
+                dict.set_item(key, json_to_py(py, v)?)?;
+            }
+            Ok(dict.into_py(py))
+        }
+    }
+}
+
+/// Execute `query` against the bindings supplied in `environment` (a dict mapping
+/// binding name to a JSON-like Python value) and return the result as native Python
+/// objects (bags/lists of tuples, mirroring how py-partiql-parser executes queries).
+#[pyfunction]
+fn evaluate_partiql(py: Python, query: &str, environment: &PyAny) -> PyResult<PyObject> {
+    let parsed = Parser::default()
+        .parse(query)
+        .map_err(|e| syntax_error(py, diagnostics::diagnostics_for(query, &e)))?;
+
+    let logical_plan = lower(&parsed)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to lower query: {:?}", e)))?;
+
+    let planner = EvaluatorPlanner::default();
+    let plan = planner
+        .compile(&logical_plan)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Failed to compile query: {:?}", e)))?;
+
+    let mut bindings = MapBindings::default();
+    let environment: &PyDict = environment
+        .downcast()
+        .map_err(|_| pyo3::exceptions::PyTypeError::new_err("environment must be a dict of binding name -> value"))?;
+    for (name, value) in environment.iter() {
+        let name: String = name.extract()?;
+        bindings.insert(&name, py_to_value(value)?);
+    }
+
+    let evaluated = plan
+        .execute_mut(&mut bindings)
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to evaluate query: {:?}", e)))?;
+
+    value_to_py(py, &evaluated.result)
+}
+
+/// True if `value` is an instance of Python's `decimal.Decimal`, not merely a string
+/// that happens to look like a number.
+fn is_decimal(value: &PyAny) -> PyResult<bool> {
+    let decimal_type = value.py().import("decimal")?.getattr("Decimal")?.downcast::<pyo3::types::PyType>()?;
+    value.is_instance(decimal_type)
+}
+
+/// Convert a Python value (bool, int, float, str, Decimal, list, dict, None) into a
+/// `partiql_value::Value`, recursing into lists and dicts.
+fn py_to_value(value: &PyAny) -> PyResult<Value> {
+    if value.is_none() {
+        return Ok(Value::Null);
+    }
+    if let Ok(b) = value.extract::<bool>() {
+        return Ok(Value::Boolean(b));
+    }
+    // Checked before the numeric extractions: `Decimal` implements `__float__`, so
+    // `extract::<f64>` would otherwise accept it and lose its exact value.
+    if is_decimal(value)? {
+        let text = value.str()?.to_string();
+        let decimal = Decimal::from_str(&text)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid Decimal: {e}")))?;
+        return Ok(Value::Decimal(Box::new(decimal)));
+    }
+    if let Ok(i) = value.extract::<i64>() {
+        return Ok(Value::Integer(i));
+    }
+    if let Ok(f) = value.extract::<f64>() {
+        return Ok(Value::Real(f.into()));
+    }
+    if let Ok(s) = value.extract::<String>() {
+        return Ok(Value::String(Box::new(s)));
+    }
+    if let Ok(list) = value.downcast::<PyList>() {
+        let mut elements = ValueList::new();
+        for item in list.iter() {
+            elements.push(py_to_value(item)?);
+        }
+        return Ok(Value::List(Box::new(elements)));
+    }
+    if let Ok(dict) = value.downcast::<PyDict>() {
+        let mut tuple = Tuple::new();
+        for (k, v) in dict.iter() {
+            let k: String = k.extract()?;
+            tuple.insert(&k, py_to_value(v)?);
+        }
+        return Ok(Value::Tuple(Box::new(tuple)));
+    }
+    Err(pyo3::exceptions::PyTypeError::new_err(format!(
+        "Unsupported value in environment: {}",
+        value
+    )))
+}
+
+/// Convert a `partiql_value::Value` back into a native Python object: bags and lists
+/// become `list`s, tuples become `dict`s, and decimals become `decimal.Decimal`.
+fn value_to_py(py: Python, value: &Value) -> PyResult<PyObject> {
+    match value {
+        Value::Null | Value::Missing => Ok(py.None()),
+        Value::Boolean(b) => Ok(b.into_py(py)),
+        Value::Integer(i) => Ok(i.into_py(py)),
+        Value::Real(f) => Ok(f64::from(*f).into_py(py)),
+        Value::Decimal(d) => {
+            let decimal_module = py.import("decimal")?;
+            Ok(decimal_module
+                .getattr("Decimal")?
+                .call1((d.to_string(),))?
+                .into_py(py))
+        }
+        Value::String(s) => Ok(s.into_py(py)),
+        Value::List(l) => {
+            let items: PyResult<Vec<PyObject>> = l.iter().map(|v| value_to_py(py, v)).collect();
+            Ok(PyList::new(py, items?).into_py(py))
+        }
+        Value::Bag(b) => {
+            let items: PyResult<Vec<PyObject>> = b.iter().map(|v| value_to_py(py, v)).collect();
+            Ok(PyList::new(py, items?).into_py(py))
+        }
+        Value::Tuple(t) => {
+            let dict = PyDict::new(py);
+            for (k, v) in t.pairs() {
+                dict.set_item(k, value_to_py(py, v)?)?;
+            }
+            Ok(dict.into_py(py))
+        }
+        other => Ok(format!("{:?}", other).into_py(py)),
+    }
+}
+
+/// Tokenize `query` without building a full AST, yielding each token's kind, its
+/// source text, and its `(start_byte, end_byte)` span. Mirrors the parser's
+/// internal `Scanner`/lexer, which is useful for syntax highlighters and editor
+/// integrations that need tokens before a statement is syntactically complete.
+#[pyfunction]
+fn scan_partiql(py: Python, query: &str) -> PyResult<Vec<PyObject>> {
+    let tokens = scanner::scan(query)
+        .map_err(|e| syntax_error(py, vec![diagnostics::diagnostic_for_lex_error(query, &e)]))?;
+
+    tokens
+        .into_iter()
+        .map(|token| {
+            let dict = PyDict::new(py);
+            dict.set_item("kind", token.kind)?;
+            dict.set_item("text", token.text)?;
+            dict.set_item("start_byte", token.start_byte)?;
+            dict.set_item("end_byte", token.end_byte)?;
+            Ok(dict.into_py(py))
+        })
+        .collect()
+}
+
 #[pymodule]
 #[pyo3(name = "partiql_parser")]
 fn parse(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(parse_partiql, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate_partiql, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_partiql_json, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_partiql_ast, m)?)?;
+    m.add_function(wrap_pyfunction!(scan_partiql, m)?)?;
+    m.add("PartiQLSyntaxError", _py.get_type::<PartiQLSyntaxError>())?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_round_trips_through_value_not_through_plain_strings() {
+        Python::with_gil(|py| {
+            let decimal = py.import("decimal").unwrap().getattr("Decimal").unwrap();
+            let value = py_to_value(decimal.call1(("02139",)).unwrap()).unwrap();
+            assert!(matches!(value, Value::Decimal(d) if d.to_string() == "2139"));
+
+            let back = value_to_py(py, &value).unwrap();
+            assert!(back.as_ref(py).is_instance(decimal.downcast().unwrap()).unwrap());
+
+            // A plain string that merely looks like a number stays a string.
+            let zip_code = py_to_value("02139".into_py(py).as_ref(py)).unwrap();
+            assert!(matches!(zip_code, Value::String(s) if *s == "02139"));
+        });
+    }
+
+    #[test]
+    fn nested_list_and_dict_round_trip() {
+        Python::with_gil(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("a", vec![1i64, 2, 3]).unwrap();
+            let value = py_to_value(dict.as_ref()).unwrap();
+            assert!(matches!(value, Value::Tuple(_)));
+
+            let back = value_to_py(py, &value).unwrap();
+            let back: &PyDict = back.downcast(py).unwrap();
+            let a: Vec<i64> = back.get_item("a").unwrap().unwrap().extract().unwrap();
+            assert_eq!(a, vec![1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn empty_bag_converts_to_empty_python_list() {
+        Python::with_gil(|py| {
+            let bag = Value::Bag(Box::new(partiql_value::Bag::new()));
+            let back = value_to_py(py, &bag).unwrap();
+            let back: &PyList = back.downcast(py).unwrap();
+            assert_eq!(back.len(), 0);
+        });
+    }
+
+    #[test]
+    fn parse_partiql_json_includes_node_byte_spans() {
+        Python::with_gil(|py| {
+            let json = parse_partiql_json(py, "SELECT a FROM t").unwrap();
+            let document: serde_json::Value = serde_json::from_str(&json).unwrap();
+            let locations = document["locations"].as_array().unwrap();
+            assert!(!locations.is_empty());
+            assert!(locations[0]["start_byte"].is_u64());
+            assert!(locations[0]["end_byte"].is_u64());
+        });
+    }
+
+    #[test]
+    fn parse_partiql_ast_walks_into_a_nested_dict() {
+        Python::with_gil(|py| {
+            let ast = parse_partiql_ast(py, "SELECT a FROM t").unwrap();
+            let ast: &PyDict = ast.downcast(py).unwrap();
+            assert!(ast.contains("ast").unwrap());
+            assert!(ast.contains("locations").unwrap());
+        });
+    }
+
+    #[test]
+    fn parse_partiql_json_raises_structured_syntax_error_on_bad_input() {
+        Python::with_gil(|py| {
+            let err = parse_partiql_json(py, "SELECT FROM").unwrap_err();
+            assert!(err.is_instance_of::<PartiQLSyntaxError>(py));
+            let errors = err.value(py).getattr("errors").unwrap();
+            let errors: &PyList = errors.downcast().unwrap();
+            assert!(!errors.is_empty());
+        });
+    }
+}
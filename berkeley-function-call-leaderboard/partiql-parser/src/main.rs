@@ -1,26 +1,140 @@
-use partiql_parser::Parser;
 use anyhow::Result;
-use std::io::{self, Write};
+use partiql_parser::{Parser, ParserResult};
+use partiql_parser_lib::{diagnostics, scanner};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+const HISTORY_FILE: &str = ".partiql_history";
+const QUIT_COMMAND: &str = "\\q";
+const SCAN_COMMAND: &str = "\\scan ";
 
 fn main() -> Result<()> {
-    // Prompt the user to enter a PartiQL query
-    print!("Please enter your PartiQL query: ");
-    io::stdout().flush()?; // Ensure the prompt is displayed before reading input
+    let colorize = supports_color();
 
-    // Read the user input
-    let mut query = String::new();
-    io::stdin().read_line(&mut query)?;
+    let mut editor = DefaultEditor::new()?;
+    let _ = editor.load_history(HISTORY_FILE);
 
-    // Trim the input to remove any extra whitespace or newline characters
-    let query = query.trim();
+    println!(
+        "PartiQL REPL. Enter a statement (terminate with `;`), `{}<query>` to tokenize, or `{}` to quit.",
+        SCAN_COMMAND, QUIT_COMMAND
+    );
 
-    // Create a new Parser instance
-    let parser = Parser::default();
+    let mut buffer = String::new();
+    loop {
+        let prompt = if buffer.is_empty() { "partiql> " } else { "     ... " };
+        match editor.readline(prompt) {
+            Ok(line) => {
+                if buffer.is_empty() && line.trim() == QUIT_COMMAND {
+                    break;
+                }
+                if buffer.is_empty() && line.starts_with(SCAN_COMMAND) {
+                    editor.add_history_entry(line.as_str())?;
+                    run_scan(&line[SCAN_COMMAND.len()..]);
+                    continue;
+                }
+                if !buffer.is_empty() {
+                    buffer.push('\n');
+                }
+                buffer.push_str(&line);
 
-    // Parse the query
-    let parsed = parser.parse(query);
+                if !is_balanced(&buffer) {
+                    continue;
+                }
 
-    // Print the parsed result
-    println!("Parsed Query: {:?}", parsed);
+                editor.add_history_entry(buffer.as_str())?;
+                run_statement(&buffer, colorize);
+                buffer.clear();
+            }
+            Err(ReadlineError::Interrupted) => {
+                buffer.clear();
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("Readline error: {e}");
+                break;
+            }
+        }
+    }
+
+    editor.save_history(HISTORY_FILE)?;
     Ok(())
 }
+
+/// A statement is balanced once its parens/brackets/braces close and it ends in `;`,
+/// which is when the REPL stops prompting for continuation lines.
+fn is_balanced(buffer: &str) -> bool {
+    let mut depth = 0i32;
+    for c in buffer.chars() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    depth <= 0 && buffer.trim_end().ends_with(';')
+}
+
+fn run_statement(query: &str, colorize: bool) {
+    let parser = Parser::default();
+    match parser.parse(query) {
+        Ok(parsed) => print_ast(&parsed, colorize),
+        Err(e) => {
+            for diagnostic in diagnostics::diagnostics_for(query, &e) {
+                eprintln!("{}", diagnostics::render_snippet(query, &diagnostic));
+            }
+        }
+    }
+}
+
+/// The `\scan` REPL command: tokenize `query` without building an AST and print each
+/// token's kind, source text, and byte span, mirroring the `scan_partiql` binding.
+fn run_scan(query: &str) {
+    match scanner::scan(query) {
+        Ok(tokens) => {
+            for token in tokens {
+                println!("{}\t{:?}\t[{}..{}]", token.kind, token.text, token.start_byte, token.end_byte);
+            }
+        }
+        Err(e) => {
+            let diagnostic = diagnostics::diagnostic_for_lex_error(query, &e);
+            eprintln!("{}", diagnostics::render_snippet(query, &diagnostic));
+        }
+    }
+}
+
+/// Pretty-print the parsed AST as an indented tree: Rust's alternate (`{:#?}`) Debug
+/// format already recurses into every child node one indentation level per level of
+/// nesting, so we reuse it and colorize each line's leading identifier on top.
+fn print_ast(parsed: &ParserResult, colorize: bool) {
+    let pretty = format!("{:#?}", parsed.ast.node);
+    for line in pretty.lines() {
+        println!("{}", colorize_line(line, colorize));
+    }
+}
+
+/// Colorize the leading identifier of one line of pretty-printed AST Debug output
+/// (a node/variant kind, or the field name on `field: value` lines), leaving
+/// indentation and the rest of the line untouched; falls back to plain text
+/// when the terminal doesn't support color.
+fn colorize_line(line: &str, colorize: bool) -> String {
+    if !colorize {
+        return line.to_string();
+    }
+    let indent_len = line.len() - line.trim_start().len();
+    let (indent, rest) = line.split_at(indent_len);
+    let kind_len = rest.chars().take_while(|c| c.is_alphanumeric() || *c == '_').count();
+    if kind_len == 0 {
+        return line.to_string();
+    }
+    let (kind, tail) = rest.split_at(kind_len);
+    format!("{indent}\x1b[1;36m{kind}\x1b[0m{tail}")
+}
+
+/// Detect whether stdout is a color-capable terminal using `supports-color`, rather
+/// than assuming one way or another.
+fn supports_color() -> bool {
+    supports_color::on(supports_color::Stream::Stdout)
+        .map(|level| level.has_basic)
+        .unwrap_or(false)
+}
@@ -0,0 +1,64 @@
+//! Shared token-scanning over the parser's lexer, used by both the Python binding
+//! (`scan_partiql`) and the CLI's `\scan` REPL command so the two entry points can't
+//! drift apart the way a copy-pasted lexer-draining loop would.
+
+use partiql_parser::lexer::{LexError, PartiqlLexer};
+
+/// One lexed token: its kind, its source text, and its byte span.
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: String,
+    pub text: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+/// Tokenize `query` without building a full AST, draining the lexer until it's
+/// exhausted or hits a lex error.
+pub fn scan(query: &str) -> Result<Vec<Token>, LexError> {
+    let mut lexer = PartiqlLexer::new(query);
+    let mut tokens = Vec::new();
+    loop {
+        match lexer.next() {
+            Some(Ok((start, token, end))) => {
+                let start_byte = start.0 as usize;
+                let end_byte = end.0 as usize;
+                tokens.push(Token {
+                    kind: format!("{:?}", token),
+                    text: query[start_byte..end_byte].to_string(),
+                    start_byte,
+                    end_byte,
+                });
+            }
+            Some(Err(e)) => return Err(e),
+            None => return Ok(tokens),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_query_tokenizes_to_no_tokens() {
+        assert!(scan("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn scans_every_token_with_its_source_span() {
+        let tokens = scan("SELECT a FROM t").unwrap();
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["SELECT", "a", "FROM", "t"]);
+        for token in &tokens {
+            assert_eq!(&"SELECT a FROM t"[token.start_byte..token.end_byte], token.text);
+        }
+    }
+
+    #[test]
+    fn unterminated_string_literal_surfaces_as_a_lex_error() {
+        let err = scan("'unterminated").unwrap_err();
+        let diagnostic = crate::diagnostics::diagnostic_for_lex_error("'unterminated", &err);
+        assert_eq!(diagnostic.start_byte, 0);
+    }
+}